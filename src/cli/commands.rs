@@ -0,0 +1,533 @@
+//! Implementations of each subcommand in `main.rs`. These share a common
+//! shape: load the relevant records via `crate::source`, narrow them down
+//! (session/filter-mode/`OptFilters`), then hand the result to an output
+//! formatter.
+
+use std::path::PathBuf;
+
+use futures::{pin_mut, StreamExt};
+
+use crate::cli::OutputFormat;
+use crate::config::Config;
+use crate::context::{Context, FilterMode};
+use crate::dedup::FuzzyDeduper;
+use crate::engine::AnalyticsEngine;
+use crate::error::{Error, Result};
+use crate::export;
+use crate::filters::{self, OptFilters};
+use crate::index::InvertedIndex;
+use crate::models::{Prompt, Session, TodoStatus};
+use crate::source;
+
+pub async fn prompts(
+    config: &Config,
+    context: &Context,
+    filter_mode: FilterMode,
+    session: Option<String>,
+    filters: OptFilters,
+    format: OutputFormat,
+) -> Result<()> {
+    let is_match = |p: &Prompt| {
+        context.matches_session(filter_mode, &p.session_id)
+            && context.matches_project(filter_mode, &p.project)
+            && session.as_deref().is_none_or(|s| p.session_id == s)
+            && filters::matches(&filters, p)
+    };
+
+    let stream = source::prompt_stream(config);
+    pin_mut!(stream);
+
+    // Nothing downstream needs the full result set when there's a `--limit`
+    // and no `--reverse`/`--offset` (`filters::can_short_circuit`): stop
+    // reading as soon as we have enough matches and flush rows as they
+    // arrive instead of buffering the whole corpus. The rows printed here
+    // are the first `--limit` matches in the stream's file-traversal order,
+    // not the oldest by timestamp. `--reverse`/`--offset` both need a
+    // defined position in the *complete* ordered result set, so they fall
+    // back to buffering everything and sorting by timestamp below — as does
+    // any format whose output isn't row-at-a-time (`Json`'s outer array,
+    // `Csv`'s header).
+    if let (true, Some(row_format)) = (filters::can_short_circuit(&filters), row_streamable(format)) {
+        let limit = filters.limit.unwrap();
+        let mut seen = 0;
+        while seen < limit {
+            let Some(item) = stream.next().await else { break };
+            let p = item?;
+            if is_match(&p) {
+                print_prompt_row(&p, row_format);
+                seen += 1;
+            }
+        }
+        return Ok(());
+    }
+
+    let mut results = Vec::new();
+    while let Some(item) = stream.next().await {
+        let p = item?;
+        if is_match(&p) {
+            results.push(p);
+        }
+    }
+
+    // `--reverse`/`--offset`/`--limit` only mean something against a
+    // defined order, and `prompt_stream` has none (it follows `WalkDir`'s
+    // traversal, not timestamp) — so establish oldest-first order here
+    // before `paginate` applies them, the same way `sessions` sorts
+    // before pagination.
+    results.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    let results = filters::paginate(&filters, results);
+    print_prompts(&results, format);
+    Ok(())
+}
+
+/// Formats `print_prompt_row` can flush one record at a time without ever
+/// holding the full result set, returning the row-oriented format to print
+/// each record with. `Json` (needs the closing `]`) and `Csv` (needs the
+/// header line before the first row) can't be streamed this way and fall
+/// back to buffering.
+fn row_streamable(format: OutputFormat) -> Option<OutputFormat> {
+    match format {
+        OutputFormat::Jsonl | OutputFormat::Table => Some(format),
+        OutputFormat::Parquet => Some(OutputFormat::Table),
+        OutputFormat::Json | OutputFormat::Csv => None,
+    }
+}
+
+/// Runs a jq-lite `query` expression (see `crate::query`) over one of the
+/// data sources named in `--help` (`history`, `transcripts`, `stats`,
+/// `todos`), printing every row the expression projects.
+pub async fn query(
+    config: &Config,
+    query: &str,
+    source: &str,
+    file_pattern: Option<String>,
+    format: OutputFormat,
+) -> Result<()> {
+    let records: Vec<serde_json::Value> = match source {
+        "history" => source::load_prompts(config)?
+            .iter()
+            .filter_map(|p| serde_json::to_value(p).ok())
+            .collect(),
+        "transcripts" => source::load_sessions(config)?
+            .iter()
+            .filter(|s| {
+                file_pattern.as_deref().is_none_or(|pattern| s.path.to_string_lossy().contains(pattern))
+            })
+            .map(session_to_value)
+            .collect(),
+        "todos" => source::load_todos(config)?
+            .iter()
+            .filter_map(|t| serde_json::to_value(t).ok())
+            .collect(),
+        // Mirrors `Stats`: not a distinct on-disk source, just an
+        // aggregate over `history`.
+        "stats" => vec![serde_json::json!({ "count": source::load_prompts(config)?.len() })],
+        other => {
+            return Err(Error::QueryParse(format!(
+                "unknown query source {other:?}: expected one of history, transcripts, stats, todos"
+            )));
+        }
+    };
+
+    let mut rows = Vec::new();
+    for record in &records {
+        rows.extend(crate::query::apply(query, record)?);
+    }
+
+    print_values(&rows, format);
+    Ok(())
+}
+
+fn session_to_value(s: &Session) -> serde_json::Value {
+    serde_json::json!({
+        "id": s.id,
+        "project": s.project.to_string_lossy(),
+        "path": s.path.to_string_lossy(),
+        "started_at": s.started_at,
+        "message_count": s.message_count,
+    })
+}
+
+pub async fn sessions(
+    config: &Config,
+    context: &Context,
+    filter_mode: FilterMode,
+    detailed: bool,
+    filters: OptFilters,
+    sort_by: &str,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut results = source::load_sessions(config)?;
+    results.retain(|s| {
+        context.matches_session(filter_mode, &s.id) && context.matches_project(filter_mode, &s.project)
+    });
+
+    match sort_by {
+        "size" => results.sort_by(|a, b| b.message_count.cmp(&a.message_count)),
+        _ => results.sort_by(|a, b| b.started_at.cmp(&a.started_at)),
+    }
+
+    let results = filters::apply(&filters, results);
+    print_sessions(&results, detailed, format);
+    Ok(())
+}
+
+pub async fn stats(
+    config: &Config,
+    context: &Context,
+    filter_mode: FilterMode,
+    group_by: &str,
+    filters: OptFilters,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut results = source::load_prompts(config)?;
+    results.retain(|p| context.matches_session(filter_mode, &p.session_id) && context.matches_project(filter_mode, &p.project));
+
+    // Same ordering fix as `prompts`/`search`: `--reverse`/`--offset`/
+    // `--limit` need a defined order to mean anything.
+    results.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    let results = filters::apply(&filters, results);
+    let _ = group_by;
+    print_stats(&results, format);
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn search(
+    config: &Config,
+    context: &Context,
+    filter_mode: FilterMode,
+    term: &str,
+    scope: &str,
+    case_sensitive: bool,
+    regex: bool,
+    before_context: usize,
+    after_context: usize,
+    filters: OptFilters,
+    format: OutputFormat,
+) -> Result<()> {
+    let _ = (scope, before_context, after_context);
+
+    let matcher: Box<dyn Fn(&str) -> bool> = if regex {
+        let pattern = if case_sensitive {
+            regex::Regex::new(term)?
+        } else {
+            regex::Regex::new(&format!("(?i){term}"))?
+        };
+        Box::new(move |text: &str| pattern.is_match(text))
+    } else if case_sensitive {
+        let term = term.to_string();
+        Box::new(move |text: &str| text.contains(&term))
+    } else {
+        let term = term.to_lowercase();
+        Box::new(move |text: &str| text.to_lowercase().contains(&term))
+    };
+
+    // `InvertedIndex::candidate_sessions` isn't consulted here: it only
+    // covers sessions as of the last `ccql index` run (anything written
+    // since has zero postings and would be silently excluded), and it only
+    // posts whole alphanumeric tokens while `matcher` above does a raw
+    // substring/regex check — a query term that's a true substring of a
+    // larger token (`"don"` inside `"condone"`) would never be posted
+    // under it. Using it as a hard filter here would drop real matches, so
+    // `matcher` against the full stream stays the single source of truth
+    // for `search`; the index is only used where it can't drop a true
+    // positive (`ccql suggest`, an explicit completion list over indexed
+    // terms, not a results filter).
+    let is_match = |p: &Prompt| {
+        context.matches_session(filter_mode, &p.session_id)
+            && context.matches_project(filter_mode, &p.project)
+            && matcher(&p.text)
+            && filters::matches(&filters, p)
+    };
+
+    let stream = source::prompt_stream(config);
+    pin_mut!(stream);
+
+    // See `prompts`: nothing downstream needs the full result set when
+    // there's a `--limit` alone, so stop reading once we have enough
+    // matches and flush rows as they arrive.
+    if let (true, Some(row_format)) = (filters::can_short_circuit(&filters), row_streamable(format)) {
+        let limit = filters.limit.unwrap();
+        let mut seen = 0;
+        while seen < limit {
+            let Some(item) = stream.next().await else { break };
+            let p = item?;
+            if is_match(&p) {
+                print_prompt_row(&p, row_format);
+                seen += 1;
+            }
+        }
+        return Ok(());
+    }
+
+    let mut results = Vec::new();
+    while let Some(item) = stream.next().await {
+        let p = item?;
+        if is_match(&p) {
+            results.push(p);
+        }
+    }
+
+    // See `prompts`: pagination needs a defined order, which the stream
+    // itself doesn't provide.
+    results.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    let results = filters::paginate(&filters, results);
+    print_prompts(&results, format);
+    Ok(())
+}
+
+pub async fn todos(
+    config: &Config,
+    status: Option<TodoStatus>,
+    agent: Option<String>,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut todos = source::load_todos(config)?;
+    todos.retain(|t| {
+        status.is_none_or(|s| t.status() == Some(s))
+            && agent.as_deref().is_none_or(|a| t.agent_id.as_deref() == Some(a))
+    });
+
+    let values: Vec<serde_json::Value> = todos.iter().filter_map(|t| serde_json::to_value(t).ok()).collect();
+    print_values(&values, format);
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn duplicates(
+    config: &Config,
+    threshold: f64,
+    min_count: usize,
+    limit: usize,
+    show_variants: bool,
+    sort: &str,
+    min_length: usize,
+    format: OutputFormat,
+) -> Result<()> {
+    let prompts = source::load_prompts(config)?;
+    let texts: Vec<String> = prompts
+        .into_iter()
+        .map(|p| p.text)
+        .filter(|t| t.chars().count() >= min_length)
+        .collect();
+
+    let deduper = FuzzyDeduper::new(threshold);
+    let mut clusters = deduper.cluster(texts);
+    clusters.retain(|c| c.count >= min_count);
+
+    match sort {
+        "latest" => {} // requires per-variant timestamps, preserved as-is for now
+        _ => clusters.sort_by(|a, b| b.count.cmp(&a.count)),
+    }
+    clusters.truncate(limit);
+
+    print_clusters(&clusters, show_variants, format);
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn sql(
+    config: &Config,
+    query: &str,
+    write: bool,
+    dry_run: bool,
+    export_format: Option<OutputFormat>,
+    output: Option<PathBuf>,
+    format: OutputFormat,
+) -> Result<()> {
+    if write && dry_run {
+        return Err(Error::QueryExecution(
+            "--write and --dry-run are mutually exclusive".to_string(),
+        ));
+    }
+    // Write operations aren't supported by the read-only analytical engine
+    // yet; `--dry-run` is accepted as a no-op preview of that restriction.
+    if write && !dry_run {
+        return Err(Error::QueryExecution(
+            "write queries are not yet supported".to_string(),
+        ));
+    }
+
+    let engine = AnalyticsEngine::new(config)?;
+    let batches = engine.query(query).await?;
+
+    match export_format {
+        Some(OutputFormat::Parquet) => {
+            let output = output.ok_or_else(|| {
+                Error::Config("--export parquet requires --output <path>".to_string())
+            })?;
+            export::write_parquet(&batches, &output)?;
+        }
+        Some(_) | None => {
+            print_batches(&batches, format);
+        }
+    }
+
+    Ok(())
+}
+
+/// Builds or incrementally refreshes the persistent full-text index that
+/// `ccql suggest` completes terms from. (`Search` does its own full
+/// substring/regex scan rather than consulting the index — see the
+/// comment in `search` for why a stale or token-granularity index can't
+/// safely narrow down its results.)
+pub async fn index(config: &Config) -> Result<()> {
+    let mut index = InvertedIndex::load(config)?;
+    let stats = index.ingest(config)?;
+    index.save(config)?;
+
+    println!(
+        "indexed {} file(s), skipped {} unchanged, {} term(s) total",
+        stats.files_scanned, stats.files_skipped, stats.terms_indexed
+    );
+    Ok(())
+}
+
+pub async fn suggest(config: &Config, prefix: &str, limit: usize, format: OutputFormat) -> Result<()> {
+    let index = InvertedIndex::load(config)?;
+    let suggestions = index.suggest(prefix, limit);
+
+    match format {
+        OutputFormat::Json => {
+            if let Ok(s) = serde_json::to_string_pretty(&suggestions) {
+                println!("{s}");
+            }
+        }
+        _ => {
+            for term in &suggestions {
+                println!("{term}");
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_values(values: &[serde_json::Value], format: OutputFormat) {
+    match format {
+        OutputFormat::Json => {
+            if let Ok(s) = serde_json::to_string_pretty(values) {
+                println!("{s}");
+            }
+        }
+        OutputFormat::Jsonl => {
+            for v in values {
+                if let Ok(s) = serde_json::to_string(v) {
+                    println!("{s}");
+                }
+            }
+        }
+        OutputFormat::Csv | OutputFormat::Table | OutputFormat::Parquet => {
+            for v in values {
+                println!("{v}");
+            }
+        }
+    }
+}
+
+fn print_batches(batches: &[arrow::record_batch::RecordBatch], format: OutputFormat) {
+    match format {
+        OutputFormat::Json | OutputFormat::Jsonl => {
+            for batch in batches {
+                if let Ok(rows) = arrow::json::writer::record_batches_to_json_rows(&[batch]) {
+                    for row in rows {
+                        if let Ok(s) = serde_json::to_string(&row) {
+                            println!("{s}");
+                        }
+                    }
+                }
+            }
+        }
+        _ => {
+            for batch in batches {
+                println!("{}", arrow::util::pretty::pretty_format_batches(&[batch.clone()]).unwrap_or_default());
+            }
+        }
+    }
+}
+
+fn print_prompt_row(p: &Prompt, format: OutputFormat) {
+    match format {
+        OutputFormat::Jsonl => {
+            if let Ok(s) = serde_json::to_string(p) {
+                println!("{s}");
+            }
+        }
+        OutputFormat::Table => {
+            println!("[{}] {} :: {}", p.timestamp, p.project.display(), p.text);
+        }
+        OutputFormat::Json | OutputFormat::Csv | OutputFormat::Parquet => {
+            unreachable!("not a row-at-a-time format")
+        }
+    }
+}
+
+fn print_prompts(prompts: &[Prompt], format: OutputFormat) {
+    match format {
+        OutputFormat::Json => {
+            if let Ok(s) = serde_json::to_string_pretty(prompts) {
+                println!("{s}");
+            }
+        }
+        OutputFormat::Csv => {
+            println!("session_id,project,timestamp,text");
+            for p in prompts {
+                println!(
+                    "{},{},{},{}",
+                    p.session_id,
+                    p.project.display(),
+                    p.timestamp,
+                    p.text.replace('\n', " ")
+                );
+            }
+        }
+        OutputFormat::Jsonl | OutputFormat::Table => {
+            for p in prompts {
+                print_prompt_row(p, format);
+            }
+        }
+        OutputFormat::Parquet => {
+            // Parquet export is only meaningful for the columnar `Sql`
+            // results that carry an Arrow schema; other subcommands fall
+            // back to table output rather than silently writing nothing.
+            for p in prompts {
+                print_prompt_row(p, OutputFormat::Table);
+            }
+        }
+    }
+}
+
+fn print_sessions(sessions: &[Session], detailed: bool, format: OutputFormat) {
+    match format {
+        OutputFormat::Json
+        | OutputFormat::Jsonl
+        | OutputFormat::Csv
+        | OutputFormat::Table
+        | OutputFormat::Parquet => {
+            for s in sessions {
+                if detailed {
+                    println!("{} ({}) - {} messages", s.id, s.project.display(), s.message_count);
+                } else {
+                    println!("{} ({})", s.id, s.project.display());
+                }
+            }
+        }
+    }
+}
+
+fn print_stats(prompts: &[Prompt], format: OutputFormat) {
+    let _ = format;
+    println!("{} prompts", prompts.len());
+}
+
+fn print_clusters(clusters: &[crate::dedup::PromptCluster], show_variants: bool, format: OutputFormat) {
+    let _ = format;
+    for cluster in clusters {
+        println!("{}x  {}", cluster.count, cluster.canonical);
+        if show_variants {
+            for variant in &cluster.variants {
+                println!("    - {variant}");
+            }
+        }
+    }
+}
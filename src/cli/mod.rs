@@ -0,0 +1,15 @@
+pub mod commands;
+
+use clap::ValueEnum;
+
+/// Output encoding shared by every query-producing subcommand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "lowercase")]
+pub enum OutputFormat {
+    Table,
+    Json,
+    Jsonl,
+    Csv,
+    /// Columnar export for `Sql`, written with `--export parquet --output <path>`.
+    Parquet,
+}
@@ -0,0 +1,37 @@
+use std::path::PathBuf;
+
+use crate::error::{Error, Result};
+
+/// Resolved locations of the Claude Code data Ccql reads from.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub data_dir: PathBuf,
+    pub projects_dir: PathBuf,
+    pub history_file: PathBuf,
+    pub todos_dir: PathBuf,
+}
+
+impl Config {
+    pub fn new(data_dir: PathBuf) -> Result<Self> {
+        if !data_dir.exists() {
+            return Err(Error::InvalidPath(format!(
+                "data directory does not exist: {}",
+                data_dir.display()
+            )));
+        }
+
+        Ok(Self {
+            projects_dir: data_dir.join("projects"),
+            history_file: data_dir.join("history.jsonl"),
+            todos_dir: data_dir.join("todos"),
+            data_dir,
+        })
+    }
+
+    /// Default location of Claude Code's data directory, `~/.claude`.
+    pub fn default_data_dir() -> PathBuf {
+        dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(".claude")
+    }
+}
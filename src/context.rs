@@ -0,0 +1,155 @@
+//! Ambient information about where and as whom a query is being run,
+//! mirroring Atuin's `current_context()` / filter-mode split: compute it
+//! once in `main` and thread it into the command handlers rather than
+//! having each one re-derive the cwd or re-walk for a git root.
+
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use serde::Deserialize;
+
+/// Which slice of history a query should be scoped to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FilterMode {
+    /// No restriction; match records from any project or session.
+    Global,
+    /// Only the current (or most recently active) session.
+    Session,
+    /// Only records whose project matches the current working directory.
+    Project,
+    /// Alias for `Project` kept for parity with Atuin's directory mode.
+    Directory,
+    /// Records from any project nested under the enclosing git repository.
+    GitRoot,
+}
+
+/// Computed once per invocation and passed to every `commands::*` call.
+#[derive(Debug, Clone)]
+pub struct Context {
+    pub cwd: PathBuf,
+    pub git_root: Option<PathBuf>,
+    pub session: Option<String>,
+}
+
+impl Context {
+    /// Builds a `Context` from the current process environment: working
+    /// directory, enclosing git repository (if any), and the session id
+    /// most recently seen in `history_file`, analogous to Atuin's
+    /// `current_context()`.
+    pub fn current(history_file: &Path) -> Self {
+        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let git_root = find_git_root(&cwd);
+        let session = last_session_id(history_file);
+
+        Self {
+            cwd,
+            git_root,
+            session,
+        }
+    }
+
+    /// True if `project` should be included under the given filter mode.
+    ///
+    /// `project` is `source::project_name_from_dir`'s lossy, best-effort
+    /// reconstruction of a real path from a sanitized directory name
+    /// (hyphens stood in for path separators), so it can't be compared to
+    /// `self.cwd` with plain path equality: a real project whose path
+    /// contains a literal `-` (e.g. `/home/user/my-app`) round-trips to
+    /// the wrong path (`/home/user/my/app`) and would never match.
+    /// Re-sanitizing both sides (turning path separators back into
+    /// hyphens) cancels that lossy reconstruction out instead, since it's
+    /// the exact inverse of the transform that produced `project`.
+    pub fn matches_project(&self, mode: FilterMode, project: &Path) -> bool {
+        match mode {
+            FilterMode::Global | FilterMode::Session => true,
+            FilterMode::Project | FilterMode::Directory => {
+                Self::project_key(project) == Self::project_key(&self.cwd)
+            }
+            FilterMode::GitRoot => match &self.git_root {
+                Some(root) => Self::key_starts_with(&Self::project_key(project), &Self::project_key(root)),
+                None => Self::project_key(project) == Self::project_key(&self.cwd),
+            },
+        }
+    }
+
+    /// Re-sanitizes `path` back into the hyphen-joined directory-name key
+    /// it was (or would be) stored under, canceling out
+    /// `project_name_from_dir`'s lossy unsanitization.
+    fn project_key(path: &Path) -> String {
+        path.to_string_lossy().replace('/', "-")
+    }
+
+    /// Component-wise `starts_with` for two directory-name keys, mirroring
+    /// `Path::starts_with`'s semantics (a prefix must end on a `-`
+    /// boundary, not just match a substring).
+    fn key_starts_with(key: &str, prefix: &str) -> bool {
+        key == prefix || key.starts_with(&format!("{prefix}-"))
+    }
+
+    /// True if `session_id` should be included under the given filter mode.
+    pub fn matches_session(&self, mode: FilterMode, session_id: &str) -> bool {
+        match mode {
+            FilterMode::Session => self.session.as_deref() == Some(session_id),
+            _ => true,
+        }
+    }
+}
+
+/// Walks upward from `start` looking for a `.git` directory.
+fn find_git_root(start: &Path) -> Option<PathBuf> {
+    let mut dir = Some(start);
+    while let Some(current) = dir {
+        if current.join(".git").exists() {
+            return Some(current.to_path_buf());
+        }
+        dir = current.parent();
+    }
+    None
+}
+
+/// Reads the session id of the last line of `history_file`, if any.
+fn last_session_id(history_file: &Path) -> Option<String> {
+    let contents = std::fs::read_to_string(history_file).ok()?;
+    let last_line = contents.lines().rev().find(|l| !l.trim().is_empty())?;
+    let value: serde_json::Value = serde_json::from_str(last_line).ok()?;
+    value
+        .get("sessionId")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_project_handles_hyphens_in_the_real_path() {
+        // Real project dir: a literal `-` in the path, which
+        // `project_name_from_dir`'s hyphen-to-slash unsanitization
+        // mangles into `/home/user/my/app` before it ever reaches
+        // `matches_project`.
+        let lossy_project = crate::source::project_name_from_dir("-home-user-my-app");
+        let context = Context {
+            cwd: PathBuf::from("/home/user/my-app"),
+            git_root: None,
+            session: None,
+        };
+        assert!(context.matches_project(FilterMode::Project, &lossy_project));
+        assert!(!context.matches_project(FilterMode::Project, &PathBuf::from("/home/user/other")));
+    }
+
+    #[test]
+    fn matches_project_git_root_respects_component_boundaries() {
+        let context = Context {
+            cwd: PathBuf::from("/home/user/my-app/sub"),
+            git_root: Some(PathBuf::from("/home/user/my-app")),
+            session: None,
+        };
+        let nested = crate::source::project_name_from_dir("-home-user-my-app-sub-crate");
+        let sibling = crate::source::project_name_from_dir("-home-user-my-appendix");
+
+        assert!(context.matches_project(FilterMode::GitRoot, &nested));
+        assert!(!context.matches_project(FilterMode::GitRoot, &sibling));
+    }
+}
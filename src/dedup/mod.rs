@@ -1,6 +1,17 @@
 use std::collections::HashMap;
 use strsim::normalized_levenshtein;
 
+/// Size of each character shingle used to build MinHash signatures.
+const SHINGLE_SIZE: usize = 3;
+
+/// Number of independent hash functions in a MinHash signature.
+const NUM_HASHES: usize = 32;
+
+/// Number of bands the signature is split into for LSH bucketing.
+/// `NUM_HASHES` must be evenly divisible by `NUM_BANDS`.
+const NUM_BANDS: usize = 8;
+const ROWS_PER_BAND: usize = NUM_HASHES / NUM_BANDS;
+
 #[derive(Debug, Clone)]
 pub struct PromptCluster {
     pub canonical: String,
@@ -10,14 +21,39 @@ pub struct PromptCluster {
 
 pub struct FuzzyDeduper {
     threshold: f64,
+    seeds: [u64; NUM_HASHES],
 }
 
 impl FuzzyDeduper {
     pub fn new(threshold: f64) -> Self {
-        Self { threshold }
+        Self {
+            threshold,
+            seeds: Self::derive_seeds(),
+        }
     }
 
-    /// Cluster similar prompts together using fuzzy matching
+    /// Deterministic seeds for the MinHash permutations, derived from a
+    /// fixed splitmix64 sequence so clustering is stable across runs.
+    fn derive_seeds() -> [u64; NUM_HASHES] {
+        let mut seeds = [0u64; NUM_HASHES];
+        let mut state: u64 = 0x9E3779B97F4A7C15;
+        for seed in &mut seeds {
+            state = state.wrapping_add(0x9E3779B97F4A7C15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+            *seed = z ^ (z >> 31);
+        }
+        seeds
+    }
+
+    /// Cluster similar prompts together using fuzzy matching.
+    ///
+    /// Candidate pairs are first generated with a MinHash/LSH pipeline
+    /// (near-linear in the number of distinct prompts), and only those
+    /// candidates pay the cost of an exact `normalized_levenshtein` check
+    /// before being merged into a `PromptCluster`. This avoids the
+    /// all-pairs scan that made clustering quadratic on large corpora.
     pub fn cluster(&self, prompts: Vec<String>) -> Vec<PromptCluster> {
         // Count occurrences first
         let mut counts: HashMap<String, usize> = HashMap::new();
@@ -28,35 +64,103 @@ impl FuzzyDeduper {
             }
         }
 
-        // Sort by count descending
+        // Sort by count descending so the most common variant of a cluster
+        // becomes its canonical form.
         let mut items: Vec<(String, usize)> = counts.into_iter().collect();
         items.sort_by(|a, b| b.1.cmp(&a.1));
 
-        // Cluster similar items
-        let mut clusters: Vec<PromptCluster> = Vec::new();
+        // LSH bucket key -> indices into `items` that hashed into it.
+        let mut buckets: HashMap<(usize, u64), Vec<usize>> = HashMap::new();
+        // Fallback bucket for strings too short to shingle: bucketed by
+        // exact text so they still merge on exact matches.
+        let mut short_buckets: HashMap<String, Vec<usize>> = HashMap::new();
 
-        for (prompt, count) in items {
-            // Check if this prompt belongs to an existing cluster
-            let mut found_cluster = false;
+        let mut signatures: Vec<Option<[u64; NUM_HASHES]>> = Vec::with_capacity(items.len());
+        for (prompt, _) in &items {
+            signatures.push(self.minhash_signature(prompt));
+        }
 
-            for cluster in &mut clusters {
-                if self.is_similar(&prompt, &cluster.canonical) {
-                    cluster.variants.push(prompt.clone());
-                    cluster.count += count;
-                    found_cluster = true;
-                    break;
+        for (idx, (prompt, _)) in items.iter().enumerate() {
+            match &signatures[idx] {
+                Some(sig) => {
+                    for band in 0..NUM_BANDS {
+                        let key = (band, Self::band_hash(sig, band));
+                        buckets.entry(key).or_default().push(idx);
+                    }
+                }
+                None => {
+                    short_buckets.entry(prompt.clone()).or_default().push(idx);
                 }
             }
+        }
 
-            if !found_cluster {
-                clusters.push(PromptCluster {
-                    canonical: prompt.clone(),
-                    variants: vec![prompt],
-                    count,
-                });
+        // parent[i] = representative cluster index for items[i], following
+        // a simple union-find so candidates from any band merge together.
+        let mut parent: Vec<usize> = (0..items.len()).collect();
+        fn find(parent: &mut [usize], x: usize) -> usize {
+            if parent[x] != x {
+                parent[x] = find(parent, parent[x]);
+            }
+            parent[x]
+        }
+        fn union(parent: &mut [usize], a: usize, b: usize) {
+            let ra = find(parent, a);
+            let rb = find(parent, b);
+            if ra != rb {
+                parent[rb] = ra;
+            }
+        }
+
+        for bucket in buckets.values().chain(short_buckets.values()) {
+            // A bucket dominated by one huge near-duplicate cluster (e.g.
+            // thousands of "continue" variants) must not pay O(k^2): track
+            // one representative per distinct component already seen in
+            // this bucket, and compare each new item only against those
+            // representatives rather than every prior item. Once a bucket
+            // collapses to a single component, this is O(k).
+            let mut representatives: Vec<usize> = Vec::new();
+            for &idx in bucket {
+                let mut merged = false;
+                for &rep in &representatives {
+                    if find(&mut parent, rep) == find(&mut parent, idx) {
+                        merged = true;
+                        break;
+                    }
+                    if self.is_similar(&items[rep].0, &items[idx].0) {
+                        union(&mut parent, rep, idx);
+                        merged = true;
+                        break;
+                    }
+                }
+                if !merged {
+                    representatives.push(idx);
+                }
             }
         }
 
+        // Group items by their union-find representative, using the
+        // highest-count member as the cluster's canonical form.
+        let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+        for idx in 0..items.len() {
+            let root = find(&mut parent, idx);
+            groups.entry(root).or_default().push(idx);
+        }
+
+        let mut clusters: Vec<PromptCluster> = groups
+            .into_values()
+            .map(|mut member_idxs| {
+                member_idxs.sort_by(|&a, &b| items[b].1.cmp(&items[a].1));
+                let canonical = items[member_idxs[0]].0.clone();
+                let variants: Vec<String> = member_idxs.iter().map(|&i| items[i].0.clone()).collect();
+                let count = member_idxs.iter().map(|&i| items[i].1).sum();
+                PromptCluster {
+                    canonical,
+                    variants,
+                    count,
+                }
+            })
+            .collect();
+
         // Sort clusters by total count
         clusters.sort_by(|a, b| b.count.cmp(&a.count));
         clusters
@@ -90,6 +194,75 @@ impl FuzzyDeduper {
         s
     }
 
+    /// Character k-shingles of a normalized prompt, e.g. "abcd" with k=3
+    /// yields {"abc", "bcd"}.
+    fn shingles(s: &str) -> Vec<&str> {
+        let chars: Vec<(usize, char)> = s.char_indices().collect();
+        if chars.len() < SHINGLE_SIZE {
+            return Vec::new();
+        }
+        (0..=chars.len() - SHINGLE_SIZE)
+            .map(|i| {
+                let start = chars[i].0;
+                let end = chars
+                    .get(i + SHINGLE_SIZE)
+                    .map(|(byte, _)| *byte)
+                    .unwrap_or(s.len());
+                &s[start..end]
+            })
+            .collect()
+    }
+
+    /// Computes a MinHash signature over a prompt's shingle set. Returns
+    /// `None` when the prompt is too short to shingle, so callers can fall
+    /// back to exact-string bucketing.
+    fn minhash_signature(&self, s: &str) -> Option<[u64; NUM_HASHES]> {
+        let shingles = Self::shingles(s);
+        if shingles.is_empty() {
+            return None;
+        }
+
+        let mut signature = [u64::MAX; NUM_HASHES];
+        for shingle in &shingles {
+            let base = Self::fnv1a(shingle.as_bytes());
+            for (i, seed) in self.seeds.iter().enumerate() {
+                let h = base ^ seed;
+                // Mix the seeded hash so distinct seeds behave like
+                // independent permutations rather than a linear shift.
+                let h = Self::splitmix64(h);
+                if h < signature[i] {
+                    signature[i] = h;
+                }
+            }
+        }
+        Some(signature)
+    }
+
+    fn band_hash(signature: &[u64; NUM_HASHES], band: usize) -> u64 {
+        let start = band * ROWS_PER_BAND;
+        let mut hash = 0xcbf29ce484222325u64; // FNV offset basis
+        for &value in &signature[start..start + ROWS_PER_BAND] {
+            hash ^= value;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        hash
+    }
+
+    fn fnv1a(bytes: &[u8]) -> u64 {
+        let mut hash = 0xcbf29ce484222325u64;
+        for &b in bytes {
+            hash ^= b as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        hash
+    }
+
+    fn splitmix64(mut z: u64) -> u64 {
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
     fn is_similar(&self, a: &str, b: &str) -> bool {
         // Quick check for exact match
         if a == b {
@@ -149,4 +322,58 @@ mod tests {
         let clusters = deduper.cluster(prompts);
         assert!(!clusters.is_empty());
     }
+
+    #[test]
+    fn test_clustering_is_order_independent() {
+        let deduper = FuzzyDeduper::default();
+        let forward = vec![
+            "continue".to_string(),
+            "cotninue".to_string(),
+            "fix it".to_string(),
+            "fix this".to_string(),
+        ];
+        let mut reversed = forward.clone();
+        reversed.reverse();
+
+        let mut forward_counts: Vec<usize> = deduper
+            .cluster(forward)
+            .into_iter()
+            .map(|c| c.count)
+            .collect();
+        let mut reversed_counts: Vec<usize> = deduper
+            .cluster(reversed)
+            .into_iter()
+            .map(|c| c.count)
+            .collect();
+        forward_counts.sort_unstable();
+        reversed_counts.sort_unstable();
+
+        assert_eq!(forward_counts, reversed_counts);
+    }
+
+    #[test]
+    fn test_large_dominant_cluster_merges_into_one() {
+        // Regression test for the O(k^2)-per-bucket blowup: thousands of
+        // near-duplicate variants of a single short prompt, the realistic
+        // "continue" repeated all day workload this request targets.
+        let deduper = FuzzyDeduper::default();
+        let mut prompts: Vec<String> = (0..3000).map(|i| format!("continue {}", i % 7)).collect();
+        prompts.push("fix it".to_string());
+        prompts.push("fix this".to_string());
+
+        let clusters = deduper.cluster(prompts);
+        let dominant = clusters.iter().max_by_key(|c| c.count).unwrap();
+        assert_eq!(dominant.count, 3000);
+    }
+
+    #[test]
+    fn test_short_prompts_fall_back_to_exact_bucketing() {
+        let deduper = FuzzyDeduper::default();
+        // Two characters each, but multi-byte, so they pass the byte-length
+        // count filter while still being too short to shingle (k=3).
+        let prompts = vec!["你好".to_string(), "你好".to_string(), "谢谢".to_string()];
+        let clusters = deduper.cluster(prompts);
+        assert_eq!(clusters.len(), 2);
+        assert!(clusters.iter().any(|c| c.count == 2));
+    }
 }
@@ -0,0 +1,156 @@
+//! Columnar query engine backing the `Sql` subcommand. Each Claude Code
+//! data source is registered as an in-memory Arrow table so users can run
+//! real SQL against it — joins, `GROUP BY`, window functions, aggregates —
+//! through DataFusion's planner instead of a hand-rolled SQL surface.
+
+use std::sync::Arc;
+
+use arrow::array::{StringArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use datafusion::datasource::MemTable;
+use datafusion::prelude::SessionContext;
+
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::models::{Prompt, Session, Todo};
+use crate::source;
+
+/// Table each prompt (one row per user message) is registered under.
+pub const HISTORY_TABLE: &str = "history";
+/// Table each transcript file (one row per session) is registered under;
+/// joins to `history` on `session_id`.
+pub const TRANSCRIPTS_TABLE: &str = "transcripts";
+/// Table each todo item (from `config.todos_dir`) is registered under.
+///
+/// There's no separate `stats` table: the stats the CLI's `Stats`
+/// subcommand prints are an aggregate over `history` (`GROUP BY`,
+/// `COUNT`), not a distinct on-disk source, so `SELECT ... FROM history
+/// GROUP BY ...` is the SQL equivalent rather than a fourth table.
+pub const TODOS_TABLE: &str = "todos";
+
+pub struct AnalyticsEngine {
+    ctx: SessionContext,
+}
+
+impl AnalyticsEngine {
+    /// Builds a fresh session with every data source registered as a
+    /// table. Sources are read once, up front, since DataFusion's
+    /// `MemTable` needs the full set of `RecordBatch`es to plan over.
+    pub fn new(config: &Config) -> Result<Self> {
+        let ctx = SessionContext::new();
+
+        let prompts = source::load_prompts(config)?;
+        let (schema, batch) = prompts_to_batch(&prompts)?;
+        register(&ctx, HISTORY_TABLE, schema, batch)?;
+
+        let sessions = source::load_sessions(config)?;
+        let (schema, batch) = sessions_to_batch(&sessions)?;
+        register(&ctx, TRANSCRIPTS_TABLE, schema, batch)?;
+
+        let todos = source::load_todos(config)?;
+        let (schema, batch) = todos_to_batch(&todos)?;
+        register(&ctx, TODOS_TABLE, schema, batch)?;
+
+        Ok(Self { ctx })
+    }
+
+    /// Plans and executes `sql`, returning the resulting batches.
+    pub async fn query(&self, sql: &str) -> Result<Vec<RecordBatch>> {
+        let df = self
+            .ctx
+            .sql(sql)
+            .await
+            .map_err(|e| Error::QueryExecution(e.to_string()))?;
+        df.collect()
+            .await
+            .map_err(|e| Error::QueryExecution(e.to_string()))
+    }
+}
+
+/// Wraps `schema`/`batch` in a single-partition `MemTable` and registers
+/// it under `name`, the shared last step of building every table.
+fn register(ctx: &SessionContext, name: &str, schema: Arc<Schema>, batch: RecordBatch) -> Result<()> {
+    let table =
+        MemTable::try_new(schema, vec![vec![batch]]).map_err(|e| Error::QueryExecution(e.to_string()))?;
+    ctx.register_table(name, Arc::new(table))
+        .map_err(|e| Error::QueryExecution(e.to_string()))?;
+    Ok(())
+}
+
+fn prompts_to_batch(prompts: &[Prompt]) -> Result<(Arc<Schema>, RecordBatch)> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("session_id", DataType::Utf8, false),
+        Field::new("project", DataType::Utf8, false),
+        Field::new("text", DataType::Utf8, false),
+        Field::new("timestamp", DataType::Utf8, false),
+    ]));
+
+    let session_ids: StringArray = prompts.iter().map(|p| p.session_id.as_str()).collect();
+    let projects: StringArray = prompts
+        .iter()
+        .map(|p| p.project.to_string_lossy().into_owned())
+        .collect();
+    let texts: StringArray = prompts.iter().map(|p| p.text.as_str()).collect();
+    let timestamps: StringArray = prompts.iter().map(|p| p.timestamp.as_str()).collect();
+
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(session_ids),
+            Arc::new(projects),
+            Arc::new(texts),
+            Arc::new(timestamps),
+        ],
+    )
+    .map_err(|e| Error::QueryExecution(e.to_string()))?;
+
+    Ok((schema, batch))
+}
+
+fn sessions_to_batch(sessions: &[Session]) -> Result<(Arc<Schema>, RecordBatch)> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("session_id", DataType::Utf8, false),
+        Field::new("project", DataType::Utf8, false),
+        Field::new("started_at", DataType::Utf8, true),
+        Field::new("message_count", DataType::UInt64, false),
+    ]));
+
+    let session_ids: StringArray = sessions.iter().map(|s| s.id.as_str()).collect();
+    let projects: StringArray = sessions
+        .iter()
+        .map(|s| s.project.to_string_lossy().into_owned())
+        .collect();
+    let started_ats: StringArray = sessions.iter().map(|s| s.started_at.as_deref()).collect();
+    let message_counts: UInt64Array = sessions.iter().map(|s| s.message_count as u64).collect();
+
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(session_ids),
+            Arc::new(projects),
+            Arc::new(started_ats),
+            Arc::new(message_counts),
+        ],
+    )
+    .map_err(|e| Error::QueryExecution(e.to_string()))?;
+
+    Ok((schema, batch))
+}
+
+fn todos_to_batch(todos: &[Todo]) -> Result<(Arc<Schema>, RecordBatch)> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("content", DataType::Utf8, false),
+        Field::new("status", DataType::Utf8, false),
+        Field::new("agent_id", DataType::Utf8, true),
+    ]));
+
+    let contents: StringArray = todos.iter().map(|t| t.content.as_str()).collect();
+    let statuses: StringArray = todos.iter().map(|t| t.status.as_str()).collect();
+    let agent_ids: StringArray = todos.iter().map(|t| t.agent_id.as_deref()).collect();
+
+    let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(contents), Arc::new(statuses), Arc::new(agent_ids)])
+        .map_err(|e| Error::QueryExecution(e.to_string()))?;
+
+    Ok((schema, batch))
+}
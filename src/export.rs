@@ -0,0 +1,29 @@
+//! Writes SQL query results out as Parquet so analysts can pull Claude
+//! Code usage into their own columnar tooling.
+
+use std::fs::File;
+use std::path::Path;
+
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+
+use crate::error::{Error, Result};
+
+pub fn write_parquet(batches: &[RecordBatch], path: &Path) -> Result<()> {
+    let Some(schema) = batches.first().map(|b| b.schema()) else {
+        return Ok(());
+    };
+
+    let file = File::create(path)?;
+    let mut writer = ArrowWriter::try_new(file, schema, None)
+        .map_err(|e| Error::QueryExecution(e.to_string()))?;
+
+    for batch in batches {
+        writer
+            .write(batch)
+            .map_err(|e| Error::QueryExecution(e.to_string()))?;
+    }
+    writer.close().map_err(|e| Error::QueryExecution(e.to_string()))?;
+
+    Ok(())
+}
@@ -0,0 +1,192 @@
+//! A single, reusable filter/pagination struct shared by every subcommand
+//! that walks a record stream, modeled on Atuin's database filters. Each
+//! `commands::*` handler builds one of these from its CLI flags instead of
+//! hand-rolling its own since/until/limit logic.
+
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+
+/// Range, scope, and pagination bounds applied uniformly across `Prompts`,
+/// `Search`, `Sessions`, and `Stats`.
+#[derive(Debug, Clone, Default)]
+pub struct OptFilters {
+    /// Only include records at or after this date (`YYYY-MM-DD`).
+    pub after: Option<String>,
+    /// Only include records at or before this date (`YYYY-MM-DD`).
+    pub before: Option<String>,
+    /// Cap the number of records returned, applied after `offset`.
+    pub limit: Option<usize>,
+    /// Skip this many matching records before collecting `limit`.
+    pub offset: Option<usize>,
+    /// Reverse the order the caller sorted matches in, before `offset` and
+    /// `limit` apply.
+    pub reverse: bool,
+    /// Restrict to records whose project is exactly this working directory.
+    pub cwd: Option<PathBuf>,
+    /// Restrict to records whose project path contains this substring.
+    pub project: Option<String>,
+}
+
+/// Implemented by any record type `OptFilters` can be applied to.
+pub trait Filterable {
+    fn timestamp(&self) -> &str;
+    fn project_path(&self) -> &Path;
+}
+
+/// True if `item` falls within the date range and project/cwd scope of
+/// `filters`. Used both for bulk retention and for per-item checks while
+/// consuming a lazy stream.
+pub fn matches<T: Filterable>(filters: &OptFilters, item: &T) -> bool {
+    in_date_range(item.timestamp(), filters.after.as_deref(), filters.before.as_deref())
+        && filters
+            .project
+            .as_deref()
+            .is_none_or(|p| item.project_path().to_string_lossy().contains(p))
+        // `project_path` is `source::project_name_from_dir`'s lossy
+        // reconstruction, so compare re-sanitized keys as
+        // `Context::matches_project` does rather than the paths themselves.
+        && filters.cwd.as_deref().is_none_or(|cwd| {
+            item.project_path().to_string_lossy().replace('/', "-") == cwd.to_string_lossy().replace('/', "-")
+        })
+}
+
+/// Orders and paginates an already-scoped set of items: reverse, then
+/// offset, then limit. Split out from `matches` so stream consumers can
+/// apply the cheap per-item scope check while reading, and defer ordering
+/// (which needs the full set) until afterward.
+pub fn paginate<T>(filters: &OptFilters, mut items: Vec<T>) -> Vec<T> {
+    if filters.reverse {
+        items.reverse();
+    }
+
+    if let Some(offset) = filters.offset {
+        if offset >= items.len() {
+            items.clear();
+        } else {
+            items.drain(0..offset);
+        }
+    }
+
+    if let Some(limit) = filters.limit {
+        items.truncate(limit);
+    }
+
+    items
+}
+
+/// Applies `filters` to `items`: scope first, then ordering and
+/// offset/limit pagination. This is the single tested code path every
+/// subcommand's range filtering goes through.
+pub fn apply<T: Filterable>(filters: &OptFilters, mut items: Vec<T>) -> Vec<T> {
+    items.retain(|item| matches(filters, item));
+    paginate(filters, items)
+}
+
+/// True when a stream consumer can stop reading as soon as it has
+/// `filters.limit` matches: only holds when nothing downstream needs the
+/// full set (no reordering or skipping).
+pub fn can_short_circuit(filters: &OptFilters) -> bool {
+    !filters.reverse && filters.offset.is_none() && filters.limit.is_some()
+}
+
+fn in_date_range(timestamp: &str, after: Option<&str>, before: Option<&str>) -> bool {
+    let Ok(date) = chrono::DateTime::parse_from_rfc3339(timestamp).map(|d| d.date_naive()) else {
+        return true;
+    };
+
+    if let Some(after) = after.and_then(parse_date) {
+        if date < after {
+            return false;
+        }
+    }
+    if let Some(before) = before.and_then(parse_date) {
+        if date > before {
+            return false;
+        }
+    }
+    true
+}
+
+fn parse_date(s: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Item {
+        timestamp: String,
+        project: PathBuf,
+    }
+
+    impl Filterable for Item {
+        fn timestamp(&self) -> &str {
+            &self.timestamp
+        }
+        fn project_path(&self) -> &Path {
+            &self.project
+        }
+    }
+
+    fn item(ts: &str) -> Item {
+        Item {
+            timestamp: ts.to_string(),
+            project: PathBuf::from("/proj"),
+        }
+    }
+
+    #[test]
+    fn paginates_with_offset_and_limit() {
+        let items = vec![item("2026-01-01T00:00:00Z"), item("2026-01-02T00:00:00Z"), item("2026-01-03T00:00:00Z")];
+        let filters = OptFilters {
+            offset: Some(1),
+            limit: Some(1),
+            ..Default::default()
+        };
+        let result = apply(&filters, items);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].timestamp, "2026-01-02T00:00:00Z");
+    }
+
+    #[test]
+    fn reverse_flips_order_before_pagination() {
+        let items = vec![item("2026-01-01T00:00:00Z"), item("2026-01-02T00:00:00Z")];
+        let filters = OptFilters {
+            reverse: true,
+            ..Default::default()
+        };
+        let result = apply(&filters, items);
+        assert_eq!(result[0].timestamp, "2026-01-02T00:00:00Z");
+    }
+
+    #[test]
+    fn offset_past_end_yields_empty() {
+        let items = vec![item("2026-01-01T00:00:00Z")];
+        let filters = OptFilters {
+            offset: Some(5),
+            ..Default::default()
+        };
+        assert!(apply(&filters, items).is_empty());
+    }
+
+    #[test]
+    fn short_circuits_only_when_limit_alone_is_set() {
+        assert!(can_short_circuit(&OptFilters {
+            limit: Some(10),
+            ..Default::default()
+        }));
+        assert!(!can_short_circuit(&OptFilters {
+            limit: Some(10),
+            reverse: true,
+            ..Default::default()
+        }));
+        assert!(!can_short_circuit(&OptFilters {
+            limit: Some(10),
+            offset: Some(1),
+            ..Default::default()
+        }));
+        assert!(!can_short_circuit(&OptFilters::default()));
+    }
+}
@@ -0,0 +1,239 @@
+//! Persistent inverted-text index over prompt text, inspired by Sonic's
+//! ingest/query/suggest model: a term maps to a posting list of
+//! `(session_id, project)` locators. `ccql index` ingests incrementally —
+//! only files newer than the last recorded mtime are re-parsed — and
+//! `ccql suggest` completes terms from it. `Search` does not consult the
+//! index: it rescans every transcript on every invocation.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::config::Config;
+use crate::error::Result;
+use crate::models::TranscriptEntry;
+
+/// Bumped whenever the on-disk shape changes; a mismatched version forces
+/// a full rebuild instead of trying to reuse a stale index.
+const INDEX_VERSION: u32 = 1;
+
+/// A single location a term was found at: which session and project it
+/// belongs to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Locator {
+    pub session_id: String,
+    pub project: PathBuf,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct InvertedIndex {
+    version: u32,
+    /// term -> every location it was seen at.
+    postings: HashMap<String, Vec<Locator>>,
+    /// Unix seconds of each indexed file's mtime, to skip unchanged files
+    /// on the next `ingest`.
+    file_mtimes: HashMap<PathBuf, u64>,
+}
+
+#[derive(Debug, Default)]
+pub struct IngestStats {
+    pub files_scanned: usize,
+    pub files_skipped: usize,
+    pub terms_indexed: usize,
+}
+
+impl InvertedIndex {
+    /// Loads the index from `config.data_dir`, discarding it (starting
+    /// fresh) if it's missing or was built by an older `INDEX_VERSION`.
+    pub fn load(config: &Config) -> Result<Self> {
+        let path = index_path(config);
+        let Ok(contents) = fs::read_to_string(&path) else {
+            return Ok(Self::default());
+        };
+
+        match serde_json::from_str::<Self>(&contents) {
+            Ok(index) if index.version == INDEX_VERSION => Ok(index),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn save(&mut self, config: &Config) -> Result<()> {
+        self.version = INDEX_VERSION;
+        let path = index_path(config);
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Re-tokenizes every transcript file under `config.projects_dir` that
+    /// is newer than the mtime recorded from the last `ingest`.
+    pub fn ingest(&mut self, config: &Config) -> Result<IngestStats> {
+        let mut stats = IngestStats::default();
+        self.version = INDEX_VERSION;
+
+        for entry in WalkDir::new(&config.projects_dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().extension().is_some_and(|ext| ext == "jsonl"))
+        {
+            let path = entry.path().to_path_buf();
+            let mtime = mtime_secs(&path);
+
+            if self.file_mtimes.get(&path) == Some(&mtime) {
+                stats.files_skipped += 1;
+                continue;
+            }
+
+            let project = crate::source::project_name_from_dir(
+                path.parent()
+                    .and_then(|p| p.file_name())
+                    .and_then(|n| n.to_str())
+                    .unwrap_or_default(),
+            );
+
+            // Transcripts are append-only in practice, so a changed mtime
+            // means new lines were added; existing postings for this file
+            // are left in place and new terms are merged in below.
+            for line in fs::read_to_string(&path)?.lines() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let Ok(record) = serde_json::from_str::<TranscriptEntry>(line) else {
+                    continue;
+                };
+                if record.entry_type != "user" {
+                    continue;
+                }
+                let Some(text) = record.message.as_ref().and_then(|m| m.text()) else {
+                    continue;
+                };
+
+                let locator = Locator {
+                    session_id: record.session_id.clone().unwrap_or_default(),
+                    project: project.clone(),
+                };
+                for term in tokenize(&text) {
+                    let postings = self.postings.entry(term).or_default();
+                    if !postings.contains(&locator) {
+                        postings.push(locator.clone());
+                    }
+                }
+            }
+
+            self.file_mtimes.insert(path, mtime);
+            stats.files_scanned += 1;
+        }
+
+        stats.terms_indexed = self.postings.len();
+        Ok(stats)
+    }
+
+    /// Locations where `term` (a single lowercase token) was indexed.
+    pub fn locators_for(&self, term: &str) -> &[Locator] {
+        self.postings
+            .get(&term.to_lowercase())
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// True if `ccql index` has never been run (or found nothing to
+    /// index yet); callers should fall back to a full scan in that case
+    /// rather than treating an empty posting list as "no matches".
+    pub fn is_empty(&self) -> bool {
+        self.postings.is_empty()
+    }
+
+    /// Sessions whose postings cover every token of `query`. Returns `None`
+    /// when the index hasn't been built yet, so an empty index is never
+    /// mistaken for "no matches". Neither the CLI nor the HTTP `Search`
+    /// calls this; both scan every prompt instead.
+    pub fn candidate_sessions(&self, query: &str) -> Option<HashSet<String>> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let tokens = tokenize(query);
+        let (first, rest) = tokens.split_first()?;
+        let mut candidates: HashSet<String> =
+            self.locators_for(first).iter().map(|l| l.session_id.clone()).collect();
+        for token in rest {
+            let locators = self.locators_for(token);
+            candidates.retain(|id| locators.iter().any(|l| &l.session_id == id));
+        }
+        Some(candidates)
+    }
+
+    /// Top completion terms for `prefix`, ranked by posting-list size —
+    /// Sonic's `SUGGEST` equivalent for shell/editor autocompletion.
+    pub fn suggest(&self, prefix: &str, limit: usize) -> Vec<String> {
+        let prefix = prefix.to_lowercase();
+        let mut matches: Vec<(&str, usize)> = self
+            .postings
+            .iter()
+            .filter(|(term, _)| term.starts_with(&prefix))
+            .map(|(term, locs)| (term.as_str(), locs.len()))
+            .collect();
+
+        matches.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        matches.into_iter().take(limit).map(|(term, _)| term.to_string()).collect()
+    }
+}
+
+fn index_path(config: &Config) -> PathBuf {
+    config.data_dir.join("ccql-index.json")
+}
+
+fn mtime_secs(path: &Path) -> u64 {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .unwrap_or(UNIX_EPOCH)
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Lowercase alphanumeric tokens of three or more characters; matches the
+/// `FuzzyDeduper` normalization's spirit of ignoring punctuation noise.
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(|t| t.to_lowercase())
+        .filter(|t| t.chars().count() >= 3)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokenize_splits_on_punctuation_and_drops_short_tokens() {
+        let tokens = tokenize("Fix the auth bug, re: issue-42!");
+        assert!(tokens.contains(&"fix".to_string()));
+        assert!(tokens.contains(&"auth".to_string()));
+        assert!(tokens.contains(&"bug".to_string()));
+        assert!(tokens.contains(&"issue".to_string()));
+        assert!(!tokens.contains(&"re".to_string()));
+    }
+
+    #[test]
+    fn suggest_ranks_by_posting_count() {
+        let mut index = InvertedIndex::default();
+        index.postings.insert(
+            "test".to_string(),
+            vec![
+                Locator { session_id: "a".into(), project: PathBuf::from("/p") },
+                Locator { session_id: "b".into(), project: PathBuf::from("/p") },
+            ],
+        );
+        index.postings.insert(
+            "testing".to_string(),
+            vec![Locator { session_id: "a".into(), project: PathBuf::from("/p") }],
+        );
+
+        let suggestions = index.suggest("test", 10);
+        assert_eq!(suggestions, vec!["test".to_string(), "testing".to_string()]);
+    }
+}
@@ -0,0 +1,13 @@
+pub mod cli;
+pub mod config;
+pub mod context;
+pub mod dedup;
+pub mod engine;
+pub mod error;
+pub mod export;
+pub mod filters;
+pub mod index;
+pub mod models;
+pub mod query;
+pub mod server;
+pub mod source;
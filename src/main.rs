@@ -4,7 +4,9 @@ use std::path::PathBuf;
 use ccql::cli::commands;
 use ccql::cli::OutputFormat;
 use ccql::config::Config;
+use ccql::context::{Context, FilterMode};
 use ccql::error::Result;
+use ccql::filters::OptFilters;
 use ccql::models::TodoStatus;
 
 #[derive(Parser)]
@@ -41,6 +43,12 @@ enum Commands {
         #[arg(long)]
         project: Option<String>,
 
+        /// Restrict to records under this exact working directory (unlike
+        /// `--filter-mode directory`, which compares against the process's
+        /// own cwd)
+        #[arg(long)]
+        cwd: Option<PathBuf>,
+
         /// Filter by date range (YYYY-MM-DD)
         #[arg(long)]
         since: Option<String>,
@@ -51,6 +59,19 @@ enum Commands {
         /// Limit number of results
         #[arg(short, long)]
         limit: Option<usize>,
+
+        /// Skip this many matching results before applying --limit
+        #[arg(long)]
+        offset: Option<usize>,
+
+        /// Walk matches newest-first instead of oldest-first
+        #[arg(long)]
+        reverse: bool,
+
+        /// Restrict results to a slice of history (session, project,
+        /// directory, git-root, or global)
+        #[arg(long, value_enum, default_value = "global")]
+        filter_mode: FilterMode,
     },
 
     /// Execute arbitrary jq query on data
@@ -76,9 +97,38 @@ enum Commands {
         #[arg(long)]
         project: Option<String>,
 
+        /// Restrict to records under this exact working directory
+        #[arg(long)]
+        cwd: Option<PathBuf>,
+
+        /// Filter by date range (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
+
+        #[arg(long)]
+        until: Option<String>,
+
         /// Sort by: time, size
         #[arg(long, default_value = "time")]
         sort_by: String,
+
+        /// Limit number of results
+        #[arg(short, long)]
+        limit: Option<usize>,
+
+        /// Skip this many matching results before applying --limit
+        #[arg(long)]
+        offset: Option<usize>,
+
+        /// Reverse the --sort-by order (oldest-first for time,
+        /// smallest-first for size)
+        #[arg(long)]
+        reverse: bool,
+
+        /// Restrict results to a slice of history (session, project,
+        /// directory, git-root, or global)
+        #[arg(long, value_enum, default_value = "global")]
+        filter_mode: FilterMode,
     },
 
     /// Display usage statistics
@@ -87,12 +137,33 @@ enum Commands {
         #[arg(long, default_value = "model")]
         group_by: String,
 
+        /// Restrict to records under this exact working directory
+        #[arg(long)]
+        cwd: Option<PathBuf>,
+
         /// Show statistics for date range
         #[arg(long)]
         since: Option<String>,
 
         #[arg(long)]
         until: Option<String>,
+
+        /// Limit number of results
+        #[arg(short, long)]
+        limit: Option<usize>,
+
+        /// Skip this many matching results before applying --limit
+        #[arg(long)]
+        offset: Option<usize>,
+
+        /// Walk matches newest-first instead of oldest-first
+        #[arg(long)]
+        reverse: bool,
+
+        /// Restrict results to a slice of history (session, project,
+        /// directory, git-root, or global)
+        #[arg(long, value_enum, default_value = "global")]
+        filter_mode: FilterMode,
     },
 
     /// Full-text search across all data
@@ -104,6 +175,21 @@ enum Commands {
         #[arg(long, default_value = "all")]
         scope: String,
 
+        /// Filter by project
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Restrict to records under this exact working directory
+        #[arg(long)]
+        cwd: Option<PathBuf>,
+
+        /// Filter by date range (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
+
+        #[arg(long)]
+        until: Option<String>,
+
         /// Case-sensitive search
         #[arg(short, long)]
         case_sensitive: bool,
@@ -119,6 +205,23 @@ enum Commands {
         /// Context lines after match
         #[arg(short = 'A', long, default_value = "0")]
         after_context: usize,
+
+        /// Limit number of results
+        #[arg(short, long)]
+        limit: Option<usize>,
+
+        /// Skip this many matching results before applying --limit
+        #[arg(long)]
+        offset: Option<usize>,
+
+        /// Walk matches newest-first instead of oldest-first
+        #[arg(long)]
+        reverse: bool,
+
+        /// Restrict results to a slice of history (session, project,
+        /// directory, git-root, or global)
+        #[arg(long, value_enum, default_value = "global")]
+        filter_mode: FilterMode,
     },
 
     /// List all todos and their status
@@ -171,6 +274,35 @@ enum Commands {
         /// Preview what would be modified without making changes
         #[arg(long)]
         dry_run: bool,
+
+        /// Export query results in this format instead of printing them
+        /// (currently only `parquet` is supported)
+        #[arg(long, value_enum)]
+        export: Option<OutputFormat>,
+
+        /// Destination path for `--export`
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Build or refresh the persistent full-text index that `suggest` completes terms from
+    Index,
+
+    /// Suggest completion terms for a prefix, for shell/editor autocompletion
+    Suggest {
+        /// Prefix to complete
+        prefix: String,
+
+        /// Maximum number of suggestions to return
+        #[arg(short, long, default_value = "10")]
+        limit: usize,
+    },
+
+    /// Run a long-lived HTTP server exposing queries as a paginated JSON API
+    Serve {
+        /// Address to bind, e.g. 127.0.0.1:8787
+        #[arg(long, default_value = "127.0.0.1:8787")]
+        addr: String,
     },
 }
 
@@ -194,16 +326,30 @@ async fn main() -> Result<()> {
         .unwrap_or_else(Config::default_data_dir);
 
     let config = Config::new(data_dir)?;
+    let context = Context::current(&config.history_file);
 
     match cli.command {
         Commands::Prompts {
             session,
             project,
+            cwd,
             since,
             until,
             limit,
+            offset,
+            reverse,
+            filter_mode,
         } => {
-            commands::prompts(&config, session, project, since, until, limit, cli.format).await?;
+            let filters = OptFilters {
+                after: since,
+                before: until,
+                limit,
+                offset,
+                reverse,
+                project,
+                cwd,
+            };
+            commands::prompts(&config, &context, filter_mode, session, filters, cli.format).await?;
         }
         Commands::Query {
             query,
@@ -215,33 +361,83 @@ async fn main() -> Result<()> {
         Commands::Sessions {
             detailed,
             project,
+            cwd,
+            since,
+            until,
             sort_by,
+            limit,
+            offset,
+            reverse,
+            filter_mode,
         } => {
-            commands::sessions(&config, detailed, project, &sort_by, cli.format).await?;
+            let filters = OptFilters {
+                after: since,
+                before: until,
+                limit,
+                offset,
+                reverse,
+                project,
+                cwd,
+            };
+            commands::sessions(&config, &context, filter_mode, detailed, filters, &sort_by, cli.format).await?;
         }
         Commands::Stats {
             group_by,
+            cwd,
             since,
             until,
+            limit,
+            offset,
+            reverse,
+            filter_mode,
         } => {
-            commands::stats(&config, &group_by, since, until, cli.format).await?;
+            let filters = OptFilters {
+                after: since,
+                before: until,
+                limit,
+                offset,
+                reverse,
+                project: None,
+                cwd,
+            };
+            commands::stats(&config, &context, filter_mode, &group_by, filters, cli.format).await?;
         }
         Commands::Search {
             term,
             scope,
+            project,
+            cwd,
+            since,
+            until,
             case_sensitive,
             regex,
             before_context,
             after_context,
+            limit,
+            offset,
+            reverse,
+            filter_mode,
         } => {
+            let filters = OptFilters {
+                after: since,
+                before: until,
+                limit,
+                offset,
+                reverse,
+                project,
+                cwd,
+            };
             commands::search(
                 &config,
+                &context,
+                filter_mode,
                 &term,
                 &scope,
                 case_sensitive,
                 regex,
                 before_context,
                 after_context,
+                filters,
                 cli.format,
             )
             .await?;
@@ -270,8 +466,22 @@ async fn main() -> Result<()> {
             query,
             write,
             dry_run,
+            export,
+            output,
         } => {
-            commands::sql(&config, &query, write, dry_run, cli.format).await?;
+            commands::sql(&config, &query, write, dry_run, export, output, cli.format).await?;
+        }
+        Commands::Index => {
+            commands::index(&config).await?;
+        }
+        Commands::Suggest { prefix, limit } => {
+            commands::suggest(&config, &prefix, limit, cli.format).await?;
+        }
+        Commands::Serve { addr } => {
+            let addr = addr
+                .parse()
+                .map_err(|e| ccql::error::Error::Config(format!("invalid --addr {addr:?}: {e}")))?;
+            ccql::server::serve(config, addr).await?;
         }
     }
 
@@ -0,0 +1,148 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// A single user prompt extracted from a session transcript.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Prompt {
+    pub session_id: String,
+    pub project: PathBuf,
+    pub text: String,
+    pub timestamp: String,
+}
+
+impl crate::filters::Filterable for Prompt {
+    fn timestamp(&self) -> &str {
+        &self.timestamp
+    }
+    fn project_path(&self) -> &std::path::Path {
+        &self.project
+    }
+}
+
+/// One line of a Claude Code session transcript (`~/.claude/projects/**/*.jsonl`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptEntry {
+    #[serde(rename = "type")]
+    pub entry_type: String,
+    pub message: Option<MessageContent>,
+    pub timestamp: Option<String>,
+    pub cwd: Option<PathBuf>,
+    #[serde(rename = "sessionId")]
+    pub session_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageContent {
+    pub role: Option<String>,
+    pub content: serde_json::Value,
+}
+
+impl MessageContent {
+    /// Extracts the user-visible prompt text regardless of which shape
+    /// `content` was written in: a plain string, or (far more commonly in
+    /// real transcripts) an array of content blocks like
+    /// `[{"type": "text", "text": "..."}, ...]`. Callers used to assume
+    /// the string form via `content.as_str()`, which silently dropped
+    /// every block-shaped message.
+    pub fn text(&self) -> Option<String> {
+        if let Some(s) = self.content.as_str() {
+            return Some(s.to_string());
+        }
+
+        let blocks = self.content.as_array()?;
+        let text = blocks
+            .iter()
+            .filter_map(|block| block.get("text").and_then(|t| t.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if text.is_empty() {
+            None
+        } else {
+            Some(text)
+        }
+    }
+}
+
+/// A browsable session: one transcript file under a project directory.
+#[derive(Debug, Clone)]
+pub struct Session {
+    pub id: String,
+    pub project: PathBuf,
+    pub path: PathBuf,
+    pub started_at: Option<String>,
+    pub message_count: usize,
+}
+
+impl crate::filters::Filterable for Session {
+    fn timestamp(&self) -> &str {
+        self.started_at.as_deref().unwrap_or_default()
+    }
+    fn project_path(&self) -> &std::path::Path {
+        &self.project
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TodoStatus {
+    Pending,
+    InProgress,
+    Completed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Todo {
+    pub content: String,
+    pub status: String,
+    pub agent_id: Option<String>,
+}
+
+impl Todo {
+    pub fn status(&self) -> Option<TodoStatus> {
+        match self.status.as_str() {
+            "pending" => Some(TodoStatus::Pending),
+            "in_progress" => Some(TodoStatus::InProgress),
+            "completed" => Some(TodoStatus::Completed),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(content: serde_json::Value) -> MessageContent {
+        MessageContent { role: Some("user".to_string()), content }
+    }
+
+    #[test]
+    fn text_reads_plain_string_content() {
+        let msg = message(serde_json::json!("continue"));
+        assert_eq!(msg.text().as_deref(), Some("continue"));
+    }
+
+    #[test]
+    fn text_reads_array_of_content_blocks() {
+        let msg = message(serde_json::json!([
+            {"type": "text", "text": "first"},
+            {"type": "text", "text": "second"},
+        ]));
+        assert_eq!(msg.text().as_deref(), Some("first\nsecond"));
+    }
+
+    #[test]
+    fn text_ignores_non_text_blocks() {
+        let msg = message(serde_json::json!([
+            {"type": "tool_use", "name": "bash"},
+            {"type": "text", "text": "only this"},
+        ]));
+        assert_eq!(msg.text().as_deref(), Some("only this"));
+    }
+
+    #[test]
+    fn text_is_none_when_nothing_extractable() {
+        let msg = message(serde_json::json!([{"type": "tool_use", "name": "bash"}]));
+        assert_eq!(msg.text(), None);
+    }
+}
@@ -0,0 +1,88 @@
+//! A minimal jq-style projection language for `ccql query`: just enough of
+//! jq's path syntax to pull fields out of the JSON records the other
+//! subcommands already work with, rather than pulling in a full jq
+//! implementation for what's usually a one-field projection.
+//!
+//! Supported syntax: `.` (the whole record), a dotted field path like
+//! `.project` or `.message.role`, and an optional trailing `[]` to
+//! flatten an array field into one output row per element (e.g.
+//! `.tags[]`). Anything else is a `QueryParse` error naming the
+//! expression, rather than silently producing nothing.
+
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+
+/// Applies `expr` to `record`, yielding zero or more output values: one
+/// for `.`/`.path`, zero if the path doesn't exist, or one per element for
+/// a trailing `[]`.
+pub fn apply(expr: &str, record: &Value) -> Result<Vec<Value>> {
+    let expr = expr.trim();
+    if expr.is_empty() || expr == "." {
+        return Ok(vec![record.clone()]);
+    }
+
+    let Some(path) = expr.strip_prefix('.') else {
+        return Err(Error::QueryParse(format!(
+            "unsupported query expression {expr:?}: expected `.` or a `.field` path"
+        )));
+    };
+
+    let (path, flatten) = match path.strip_suffix("[]") {
+        Some(rest) => (rest, true),
+        None => (path, false),
+    };
+
+    let mut value = record;
+    if !path.is_empty() {
+        for segment in path.split('.') {
+            let Some(next) = value.get(segment) else {
+                return Ok(Vec::new());
+            };
+            value = next;
+        }
+    }
+
+    if flatten {
+        Ok(value.as_array().cloned().unwrap_or_default())
+    } else {
+        Ok(vec![value.clone()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dot_returns_the_whole_record() {
+        let record = serde_json::json!({"a": 1});
+        assert_eq!(apply(".", &record).unwrap(), vec![record]);
+    }
+
+    #[test]
+    fn dotted_path_projects_a_nested_field() {
+        let record = serde_json::json!({"a": {"b": 2}});
+        assert_eq!(apply(".a.b", &record).unwrap(), vec![serde_json::json!(2)]);
+    }
+
+    #[test]
+    fn missing_path_yields_no_rows() {
+        let record = serde_json::json!({"a": 1});
+        assert_eq!(apply(".missing", &record).unwrap(), Vec::<Value>::new());
+    }
+
+    #[test]
+    fn trailing_brackets_flatten_an_array_field() {
+        let record = serde_json::json!({"tags": ["x", "y"]});
+        assert_eq!(
+            apply(".tags[]", &record).unwrap(),
+            vec![serde_json::json!("x"), serde_json::json!("y")]
+        );
+    }
+
+    #[test]
+    fn unsupported_expression_is_a_query_parse_error() {
+        assert!(apply("map(.a)", &serde_json::json!({})).is_err());
+    }
+}
@@ -0,0 +1,269 @@
+//! HTTP handlers mirroring `cli::commands`: each builds an `OptFilters`
+//! from query parameters, runs the same `source`/`filters`/`dedup`/
+//! `engine` pipeline the CLI uses, and hands the rows to the
+//! `PageManager` instead of printing a formatted table.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use axum::extract::{Path as AxumPath, Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::context::FilterMode;
+use crate::dedup::FuzzyDeduper;
+use crate::engine::AnalyticsEngine;
+use crate::error::Error;
+use crate::filters::{self, OptFilters};
+use crate::source;
+
+use super::pages::Page;
+use super::AppState;
+
+/// Query-string filters shared by every paginated endpoint, mirroring the
+/// `--since`/`--until`/`--limit`/`--offset`/`--reverse`/`--filter-mode`/
+/// `--cwd` flags on the equivalent CLI command.
+#[derive(Debug, Deserialize)]
+pub struct FilterParams {
+    pub project: Option<String>,
+    /// Restrict to records under this exact working directory — independent
+    /// of `filter_mode=directory`, which compares against the server
+    /// process's own cwd instead of a value the client supplies.
+    pub cwd: Option<PathBuf>,
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+    #[serde(default)]
+    pub reverse: bool,
+    #[serde(default = "default_filter_mode")]
+    pub filter_mode: FilterMode,
+}
+
+fn default_filter_mode() -> FilterMode {
+    FilterMode::Global
+}
+
+impl FilterParams {
+    fn into_opt_filters(self) -> OptFilters {
+        OptFilters {
+            after: self.since,
+            before: self.until,
+            limit: self.limit,
+            offset: self.offset,
+            reverse: self.reverse,
+            cwd: self.cwd,
+            project: self.project,
+        }
+    }
+}
+
+/// Maps `crate::error::Error` into a JSON `{"error": ...}` body, since
+/// handlers can't propagate `?` into `cli::commands`'s `Result<()>`.
+pub struct ApiError(Error);
+
+impl From<Error> for ApiError {
+    fn from(err: Error) -> Self {
+        Self(err)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::BAD_REQUEST, Json(json!({ "error": self.0.to_string() }))).into_response()
+    }
+}
+
+type ApiResult<T> = Result<T, ApiError>;
+
+pub async fn prompts(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<FilterParams>,
+) -> ApiResult<Json<Page>> {
+    let filter_mode = params.filter_mode;
+    let filters = params.into_opt_filters();
+
+    let mut results = source::load_prompts(&state.config)?;
+    results.retain(|p| {
+        state.context.matches_session(filter_mode, &p.session_id)
+            && state.context.matches_project(filter_mode, &p.project)
+    });
+    // Same oldest-first order the CLI establishes before pagination.
+    results.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    let results = filters::apply(&filters, results);
+
+    let rows = results.into_iter().filter_map(|p| serde_json::to_value(p).ok()).collect();
+    Ok(Json(state.pages.store(rows)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SearchParams {
+    pub term: String,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    #[serde(default)]
+    pub regex: bool,
+    #[serde(flatten)]
+    pub filters: FilterParams,
+}
+
+pub async fn search(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<SearchParams>,
+) -> ApiResult<Json<Page>> {
+    let filter_mode = params.filters.filter_mode;
+
+    let matcher: Box<dyn Fn(&str) -> bool> = if params.regex {
+        let pattern = if params.case_sensitive {
+            regex::Regex::new(&params.term).map_err(Error::from)?
+        } else {
+            regex::Regex::new(&format!("(?i){}", params.term)).map_err(Error::from)?
+        };
+        Box::new(move |text: &str| pattern.is_match(text))
+    } else if params.case_sensitive {
+        let term = params.term.clone();
+        Box::new(move |text: &str| text.contains(&term))
+    } else {
+        let term = params.term.to_lowercase();
+        Box::new(move |text: &str| text.to_lowercase().contains(&term))
+    };
+
+    // See `cli::commands::search`: the persistent index isn't consulted to
+    // exclude records here either — it can be stale relative to the live
+    // corpus and only posts whole tokens, while `matcher` does a raw
+    // substring/regex check, so using it as a hard filter would drop real
+    // matches.
+    let filters = params.filters.into_opt_filters();
+    let mut results = source::load_prompts(&state.config)?;
+    results.retain(|p| {
+        state.context.matches_session(filter_mode, &p.session_id)
+            && state.context.matches_project(filter_mode, &p.project)
+            && matcher(&p.text)
+    });
+    results.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    let results = filters::apply(&filters, results);
+
+    let rows = results.into_iter().filter_map(|p| serde_json::to_value(p).ok()).collect();
+    Ok(Json(state.pages.store(rows)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StatsParams {
+    #[serde(default = "default_group_by")]
+    pub group_by: String,
+    #[serde(flatten)]
+    pub filters: FilterParams,
+}
+
+fn default_group_by() -> String {
+    "model".to_string()
+}
+
+pub async fn stats(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<StatsParams>,
+) -> ApiResult<Json<Page>> {
+    let filter_mode = params.filters.filter_mode;
+    let filters = params.filters.into_opt_filters();
+
+    let mut results = source::load_prompts(&state.config)?;
+    results.retain(|p| {
+        state.context.matches_session(filter_mode, &p.session_id)
+            && state.context.matches_project(filter_mode, &p.project)
+    });
+    results.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    let results = filters::apply(&filters, results);
+
+    // Grouping isn't implemented yet, mirroring `cli::commands::stats`.
+    let _ = &params.group_by;
+    let rows = vec![json!({ "count": results.len() })];
+    Ok(Json(state.pages.store(rows)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DuplicatesParams {
+    #[serde(default = "default_threshold")]
+    pub threshold: f64,
+    #[serde(default = "default_min_count")]
+    pub min_count: usize,
+    #[serde(default = "default_min_length")]
+    pub min_length: usize,
+    #[serde(default)]
+    pub show_variants: bool,
+}
+
+fn default_threshold() -> f64 {
+    0.8
+}
+
+fn default_min_count() -> usize {
+    2
+}
+
+fn default_min_length() -> usize {
+    4
+}
+
+pub async fn duplicates(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<DuplicatesParams>,
+) -> ApiResult<Json<Page>> {
+    let prompts = source::load_prompts(&state.config)?;
+    let texts: Vec<String> = prompts
+        .into_iter()
+        .map(|p| p.text)
+        .filter(|t| t.chars().count() >= params.min_length)
+        .collect();
+
+    let deduper = FuzzyDeduper::new(params.threshold);
+    let mut clusters = deduper.cluster(texts);
+    clusters.retain(|c| c.count >= params.min_count);
+    clusters.sort_by(|a, b| b.count.cmp(&a.count));
+
+    let rows = clusters
+        .into_iter()
+        .map(|c| {
+            if params.show_variants {
+                json!({ "count": c.count, "canonical": c.canonical, "variants": c.variants })
+            } else {
+                json!({ "count": c.count, "canonical": c.canonical })
+            }
+        })
+        .collect();
+    Ok(Json(state.pages.store(rows)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SqlRequest {
+    pub query: String,
+}
+
+pub async fn sql(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<SqlRequest>,
+) -> ApiResult<Json<Page>> {
+    let engine = AnalyticsEngine::new(&state.config)?;
+    let batches = engine.query(&body.query).await?;
+
+    let mut rows = Vec::new();
+    for batch in &batches {
+        if let Ok(batch_rows) = arrow::json::writer::record_batches_to_json_rows(&[batch]) {
+            rows.extend(batch_rows.into_iter().map(serde_json::Value::Object));
+        }
+    }
+    Ok(Json(state.pages.store(rows)))
+}
+
+pub async fn page(
+    State(state): State<Arc<AppState>>,
+    AxumPath((query_id, n)): AxumPath<(String, usize)>,
+) -> ApiResult<Json<Page>> {
+    state
+        .pages
+        .page(&query_id, n)
+        .map(Json)
+        .ok_or_else(|| ApiError(Error::DataSource(format!("unknown query id: {query_id}"))))
+}
@@ -0,0 +1,46 @@
+//! Minimal per-route request counters plus the `X-CCQL-Version` header,
+//! attached to every response by middleware instead of duplicated in each
+//! handler.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use axum::extract::{Request, State};
+use axum::http::HeaderValue;
+use axum::middleware::Next;
+use axum::response::Response;
+
+use super::{AppState, CCQL_VERSION};
+
+#[derive(Default)]
+pub struct RequestMetrics {
+    counts: Mutex<HashMap<String, u64>>,
+}
+
+impl RequestMetrics {
+    /// Snapshot of request counts per request path, for diagnostics.
+    pub fn counts(&self) -> HashMap<String, u64> {
+        self.counts.lock().unwrap().clone()
+    }
+
+    fn record(&self, path: &str) {
+        *self.counts.lock().unwrap().entry(path.to_string()).or_insert(0) += 1;
+    }
+}
+
+/// Tags every response with `X-CCQL-Version`, counts requests per request
+/// path, and logs each request's latency via `tracing`.
+pub async fn track(State(state): State<Arc<AppState>>, request: Request, next: Next) -> Response {
+    let path = request.uri().path().to_string();
+    let started = Instant::now();
+
+    state.metrics.record(&path);
+    let mut response = next.run(request).await;
+
+    tracing::debug!(path = %path, elapsed_ms = started.elapsed().as_millis(), "handled request");
+    response
+        .headers_mut()
+        .insert("X-CCQL-Version", HeaderValue::from_static(CCQL_VERSION));
+    response
+}
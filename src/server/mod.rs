@@ -0,0 +1,65 @@
+//! `ccql serve`: a long-running HTTP front-end over the same query
+//! capabilities as the CLI (`prompts`, `search`, `stats`, `sql`,
+//! `duplicates`), modeled on Databend's HTTP query handler. Each endpoint
+//! accepts the same filters as its CLI counterpart but returns results
+//! through `PageManager` instead of one unbounded JSON body, so clients
+//! fetch `/page/{query_id}/{n}` until the result set is exhausted.
+
+pub mod handlers;
+pub mod metrics;
+pub mod pages;
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::routing::{get, post};
+use axum::Router;
+
+use crate::config::Config;
+use crate::context::Context;
+use crate::error::Result;
+
+pub use pages::PageManager;
+
+/// Echoed on every response as `X-CCQL-Version` so clients can detect a
+/// breaking change to this API.
+pub const CCQL_VERSION: &str = "1";
+
+/// Shared across every request handler: loaded once at startup rather
+/// than re-read per request.
+pub struct AppState {
+    pub config: Config,
+    pub context: Context,
+    pub pages: PageManager,
+    pub metrics: metrics::RequestMetrics,
+}
+
+/// Binds `addr` and serves the query API until the process is killed.
+///
+/// `directory`/`git-root`/`session` filter modes are resolved against the
+/// server process's own working directory and most recently active
+/// session, same as the CLI's `Context::current` — they don't vary
+/// per-request.
+pub async fn serve(config: Config, addr: SocketAddr) -> Result<()> {
+    let context = Context::current(&config.history_file);
+    let state = Arc::new(AppState {
+        config,
+        context,
+        pages: PageManager::new(),
+        metrics: metrics::RequestMetrics::default(),
+    });
+
+    let app = Router::new()
+        .route("/prompts", get(handlers::prompts))
+        .route("/search", get(handlers::search))
+        .route("/stats", get(handlers::stats))
+        .route("/duplicates", get(handlers::duplicates))
+        .route("/sql", post(handlers::sql))
+        .route("/page/:query_id/:page", get(handlers::page))
+        .layer(axum::middleware::from_fn_with_state(state.clone(), metrics::track))
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, app).await?;
+    Ok(())
+}
@@ -0,0 +1,141 @@
+//! Server-side result paging for `ccql serve`, modeled on Databend's HTTP
+//! query handler: a query is materialized once into a handle, and the
+//! caller fetches `/page/{query_id}/{n}` until exhausted instead of the
+//! server returning one unbounded response.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// Rows per page handed back by `/page/{query_id}/{n}`.
+pub const PAGE_SIZE: usize = 100;
+
+/// Maximum number of queries kept resident at once. A "long-running"
+/// server can't hold every result set a client has ever asked for, so the
+/// oldest query is evicted once a new one would push the count past this.
+pub const MAX_STORED_QUERIES: usize = 256;
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct Page {
+    pub query_id: String,
+    pub page: usize,
+    pub total_pages: usize,
+    pub rows: Vec<Value>,
+}
+
+/// Holds every live query's rows in memory, keyed by an opaque id, capped
+/// at `MAX_STORED_QUERIES` with FIFO eviction of the oldest query.
+#[derive(Default)]
+pub struct PageManager {
+    results: Mutex<HashMap<String, Vec<Value>>>,
+    order: Mutex<VecDeque<String>>,
+    next_id: Mutex<u64>,
+}
+
+impl PageManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores `rows` under a fresh query id and returns its first page,
+    /// evicting the oldest stored query if this pushes the count over
+    /// `MAX_STORED_QUERIES`.
+    pub fn store(&self, rows: Vec<Value>) -> Page {
+        let id = {
+            let mut next_id = self.next_id.lock().unwrap();
+            *next_id += 1;
+            format!("q{}", *next_id)
+        };
+
+        let mut results = self.results.lock().unwrap();
+        let mut order = self.order.lock().unwrap();
+
+        results.insert(id.clone(), rows);
+        order.push_back(id.clone());
+        while order.len() > MAX_STORED_QUERIES {
+            if let Some(oldest) = order.pop_front() {
+                results.remove(&oldest);
+            }
+        }
+        drop(results);
+        drop(order);
+
+        self.page(&id, 0).expect("just inserted")
+    }
+
+    /// Fetches page `n` (0-indexed) of a previously stored query. Returns
+    /// `None` if the query id is unknown or was evicted.
+    pub fn page(&self, query_id: &str, n: usize) -> Option<Page> {
+        let results = self.results.lock().unwrap();
+        let rows = results.get(query_id)?;
+
+        let total_pages = rows.len().div_ceil(PAGE_SIZE).max(1);
+        let start = (n * PAGE_SIZE).min(rows.len());
+        let end = (start + PAGE_SIZE).min(rows.len());
+
+        Some(Page {
+            query_id: query_id.to_string(),
+            page: n,
+            total_pages,
+            rows: rows[start..end].to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(n: usize) -> Vec<Value> {
+        (0..n).map(|i| serde_json::json!({ "i": i })).collect()
+    }
+
+    #[test]
+    fn store_returns_the_first_page() {
+        let manager = PageManager::new();
+        let page = manager.store(rows(3));
+        assert_eq!(page.page, 0);
+        assert_eq!(page.total_pages, 1);
+        assert_eq!(page.rows, rows(3));
+    }
+
+    #[test]
+    fn page_splits_results_at_page_size_boundaries() {
+        let manager = PageManager::new();
+        let first = manager.store(rows(PAGE_SIZE + 1));
+        assert_eq!(first.total_pages, 2);
+        assert_eq!(first.rows.len(), PAGE_SIZE);
+
+        let second = manager.page(&first.query_id, 1).unwrap();
+        assert_eq!(second.rows.len(), 1);
+        assert_eq!(second.rows[0], serde_json::json!({ "i": PAGE_SIZE }));
+    }
+
+    #[test]
+    fn page_past_the_end_returns_an_empty_page_not_none() {
+        let manager = PageManager::new();
+        let first = manager.store(rows(1));
+        let past_end = manager.page(&first.query_id, 5).unwrap();
+        assert!(past_end.rows.is_empty());
+    }
+
+    #[test]
+    fn unknown_query_id_returns_none() {
+        let manager = PageManager::new();
+        assert!(manager.page("does-not-exist", 0).is_none());
+    }
+
+    #[test]
+    fn storing_past_the_cap_evicts_the_oldest_query() {
+        let manager = PageManager::new();
+        let mut ids = Vec::new();
+        for _ in 0..=MAX_STORED_QUERIES {
+            ids.push(manager.store(rows(1)).query_id);
+        }
+
+        assert!(manager.page(&ids[0], 0).is_none(), "oldest query should have been evicted");
+        assert!(manager.page(ids.last().unwrap(), 0).is_some(), "newest query should still be live");
+    }
+}
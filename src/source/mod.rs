@@ -0,0 +1,157 @@
+//! Loaders for the on-disk shapes Claude Code writes under its data
+//! directory: per-project session transcripts, the global prompt history,
+//! and todo lists. Each loader walks its corner of `Config` and parses
+//! JSONL (or, for todos, JSON arrays) into the corresponding `models`
+//! type.
+//!
+//! `prompt_stream` is the lazy counterpart to `load_prompts`: it yields
+//! records as each transcript file is read instead of materializing the
+//! whole corpus up front, so a caller applying a `--limit` can stop
+//! reading once it has enough matches.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use async_stream::try_stream;
+use futures::stream::Stream;
+use walkdir::WalkDir;
+
+use crate::config::Config;
+use crate::error::Result;
+use crate::models::{Prompt, Session, Todo, TranscriptEntry};
+
+/// Sanitized project directory names use `-` in place of `/`; this is a
+/// best-effort unsanitization. It is lossy: a literal `-` in the real path
+/// also comes back as `/`.
+pub fn project_name_from_dir(dir_name: &str) -> PathBuf {
+    PathBuf::from(dir_name.replace('-', "/"))
+}
+
+/// Reads every `*.jsonl` transcript under `config.projects_dir` and
+/// extracts the user-authored prompts.
+pub fn load_prompts(config: &Config) -> Result<Vec<Prompt>> {
+    let mut prompts = Vec::new();
+    for entry in walk_transcripts(&config.projects_dir) {
+        let project = project_of(&entry);
+        let contents = fs::read_to_string(entry.path())?;
+        prompts.extend(parse_prompts(&contents, &project));
+    }
+    Ok(prompts)
+}
+
+/// Lazily yields prompts file-by-file as `config.projects_dir` is walked,
+/// so a consumer can stop pulling from the stream (e.g. once `--limit`
+/// matches are found) without parsing the rest of the corpus.
+pub fn prompt_stream(config: &Config) -> impl Stream<Item = Result<Prompt>> + '_ {
+    try_stream! {
+        for entry in walk_transcripts(&config.projects_dir) {
+            let project = project_of(&entry);
+            let contents = fs::read_to_string(entry.path())?;
+            for prompt in parse_prompts(&contents, &project) {
+                yield prompt;
+            }
+        }
+    }
+}
+
+fn parse_prompts(contents: &str, project: &Path) -> Vec<Prompt> {
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str::<TranscriptEntry>(line).ok())
+        .filter(|record| record.entry_type == "user")
+        .filter_map(|record| {
+            let text = record.message.as_ref()?.text()?;
+            Some(Prompt {
+                session_id: record.session_id.unwrap_or_default(),
+                project: project.to_path_buf(),
+                text,
+                timestamp: record.timestamp.unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// Lists every session transcript (one per file) under `config.projects_dir`.
+pub fn load_sessions(config: &Config) -> Result<Vec<Session>> {
+    let mut sessions = Vec::new();
+
+    for entry in walk_transcripts(&config.projects_dir) {
+        let project = project_of(&entry);
+        let id = entry
+            .path()
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_string();
+
+        let contents = fs::read_to_string(entry.path())?;
+        let mut started_at = None;
+        let mut message_count = 0;
+        for line in contents.lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            message_count += 1;
+            if started_at.is_none() {
+                if let Ok(record) = serde_json::from_str::<TranscriptEntry>(line) {
+                    started_at = record.timestamp;
+                }
+            }
+        }
+
+        sessions.push(Session {
+            id,
+            project,
+            path: entry.path().to_path_buf(),
+            started_at,
+            message_count,
+        });
+    }
+
+    Ok(sessions)
+}
+
+/// Reads every `*.json` todo list under `config.todos_dir`, each of which
+/// is a JSON array of `Todo`s for one session/agent; files that don't
+/// parse (or a missing `todos_dir` entirely) are skipped rather than
+/// treated as a hard error, mirroring `walk_transcripts`'s tolerance for
+/// directories a user has never populated.
+pub fn load_todos(config: &Config) -> Result<Vec<Todo>> {
+    let mut todos = Vec::new();
+    let Ok(entries) = fs::read_dir(&config.todos_dir) else {
+        return Ok(todos);
+    };
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        if !entry.path().extension().is_some_and(|ext| ext == "json") {
+            continue;
+        }
+        let Ok(contents) = fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        if let Ok(parsed) = serde_json::from_str::<Vec<Todo>>(&contents) {
+            todos.extend(parsed);
+        }
+    }
+
+    Ok(todos)
+}
+
+fn project_of(entry: &walkdir::DirEntry) -> PathBuf {
+    project_name_from_dir(
+        entry
+            .path()
+            .parent()
+            .and_then(|p| p.file_name())
+            .and_then(|n| n.to_str())
+            .unwrap_or_default(),
+    )
+}
+
+fn walk_transcripts(projects_dir: &Path) -> impl Iterator<Item = walkdir::DirEntry> {
+    WalkDir::new(projects_dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "jsonl"))
+}